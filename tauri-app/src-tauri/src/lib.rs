@@ -1,27 +1,232 @@
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub mod stats;
+
+/// Crate-wide error type, covering both stats persistence and in-game
+/// validation failures so every `#[tauri::command]` can return a single
+/// `Result<_, GameError>` the frontend knows how to display.
+#[derive(Debug, thiserror::Error)]
+pub enum GameError {
+    #[error("failed to read or write stats file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to (de)serialize stats: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("guess must be between {min} and {max}")]
+    OutOfRange { min: u32, max: u32 },
+    #[error("the game is already solved, start a new one")]
+    AlreadySolved,
+    #[error("invalid range: minimum ({min}) must not be greater than maximum ({max})")]
+    InvalidRange { min: u32, max: u32 },
+}
+
+// Tauri commands require their error type to implement `Serialize`; forward
+// to the `Display` message so the frontend gets the same text as the logs.
+impl serde::Serialize for GameError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// A guess validated against the active range, mirroring the bounds-check
+/// pattern from `Guess::new` in the Rust Book.
+pub struct Guess {
+    value: u32,
+}
+
+impl Guess {
+    pub fn new(value: u32, min: u32, max: u32) -> Result<Guess, GameError> {
+        if value < min || value > max {
+            return Err(GameError::OutOfRange { min, max });
+        }
+        Ok(Guess { value })
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+/// Selectable game difficulty, each mapping to a default guessing range.
+/// `Custom` lets the frontend pick an arbitrary `(min, max)` range instead.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "range")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Custom { min: u32, max: u32 },
+}
+
+impl Difficulty {
+    pub fn range(&self) -> (u32, u32) {
+        match *self {
+            Difficulty::Easy => (1, 50),
+            Difficulty::Medium => (1, 100),
+            Difficulty::Hard => (1, 1000),
+            Difficulty::Custom { min, max } => (min, max),
+        }
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Difficulty::Medium
+    }
+}
 
 #[derive(Clone, serde::Serialize)]
 pub struct GameState {
     target_number: u32,
     attempts: u32,
+    solved: bool,
+    min: u32,
+    max: u32,
+    /// Tightest range consistent with the guesses made so far, used to
+    /// build the `guess-feedback` and `hint` event payloads.
+    lower_bound: u32,
+    upper_bound: u32,
+    #[serde(skip)]
+    last_guess_at: Instant,
+    #[serde(skip)]
+    hinted: bool,
+    /// Seed used to draw `target_number`, if the game was started
+    /// deterministically (e.g. via `--seed`), kept around so the same game
+    /// can be replayed later.
+    seed: Option<u64>,
 }
 
 impl GameState {
     pub fn new() -> Self {
-        let target_number = rand::thread_rng().gen_range(1..=100);
-        GameState {
+        Self::with_range(1, 100).expect("1..=100 is always a valid range")
+    }
+
+    pub fn with_range(min: u32, max: u32) -> Result<Self, GameError> {
+        Self::with_range_and_seed(min, max, None)
+    }
+
+    pub fn with_range_and_seed(min: u32, max: u32, seed: Option<u64>) -> Result<Self, GameError> {
+        if min > max {
+            return Err(GameError::InvalidRange { min, max });
+        }
+        let target_number = match seed {
+            Some(seed) => rand::rngs::StdRng::seed_from_u64(seed).gen_range(min..=max),
+            None => rand::thread_rng().gen_range(min..=max),
+        };
+        Ok(GameState {
             target_number,
             attempts: 0,
-        }
+            solved: false,
+            min,
+            max,
+            lower_bound: min,
+            upper_bound: max,
+            last_guess_at: Instant::now(),
+            hinted: false,
+            seed,
+        })
     }
 
-    pub fn guess(&mut self, guess: u32) -> (String, u32) {
+    pub fn guess(&mut self, guess: u32) -> Result<(String, u32), GameError> {
+        if self.solved {
+            return Err(GameError::AlreadySolved);
+        }
+        let guess = Guess::new(guess, self.min, self.max)?;
+
         self.attempts += 1;
-        let result = match guess.cmp(&self.target_number) {
-            std::cmp::Ordering::Less => format!("Too low! Attempt {}", self.attempts),
-            std::cmp::Ordering::Greater => format!("Too high! Attempt {}", self.attempts),
-            std::cmp::Ordering::Equal => format!("Congratulations! You guessed the number in {} attempts!", self.attempts),
+        self.last_guess_at = Instant::now();
+        self.hinted = false;
+        let result = match guess.value().cmp(&self.target_number) {
+            std::cmp::Ordering::Less => {
+                self.lower_bound = self.lower_bound.max(guess.value() + 1);
+                format!("Too low! Attempt {}", self.attempts)
+            }
+            std::cmp::Ordering::Greater => {
+                self.upper_bound = self.upper_bound.min(guess.value().saturating_sub(1));
+                format!("Too high! Attempt {}", self.attempts)
+            }
+            std::cmp::Ordering::Equal => {
+                self.solved = true;
+                self.lower_bound = self.target_number;
+                self.upper_bound = self.target_number;
+                format!("Congratulations! You guessed the number in {} attempts!", self.attempts)
+            }
         };
-        (result, self.attempts)
+        Ok((result, self.attempts))
+    }
+
+    /// `true` once the equal-comparison branch of `guess` has fired, i.e.
+    /// the current game is finished and ready to be recorded.
+    pub fn solved(&self) -> bool {
+        self.solved
     }
+
+    /// Tightest `(min, max)` range consistent with the guesses made so far.
+    pub fn bounds(&self) -> (u32, u32) {
+        (self.lower_bound, self.upper_bound)
+    }
+
+    /// Number of guesses made so far in the current game.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Seed the game was started with, if launched deterministically.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Whether the player has gone `stall` without a guess and hasn't
+    /// already been given a hint for the current stall period.
+    pub fn should_hint(&self, stall: Duration) -> bool {
+        !self.solved && !self.hinted && self.last_guess_at.elapsed() >= stall
+    }
+
+    /// A hint about the target: an even target always gets the parity hint,
+    /// an odd one a hint relative to the midpoint of the remaining bounds.
+    pub fn hint(&mut self) -> String {
+        self.hinted = true;
+        if self.target_number % 2 == 0 {
+            "The number is even.".to_string()
+        } else {
+            let midpoint = self.lower_bound + (self.upper_bound - self.lower_bound) / 2;
+            if self.target_number > midpoint {
+                format!("The number is higher than {midpoint}.")
+            } else {
+                format!("The number is lower than {}.", midpoint + 1)
+            }
+        }
+    }
+}
+
+/// Payload for the `guess-feedback` event emitted after every guess.
+#[derive(Clone, serde::Serialize)]
+pub struct GuessFeedback {
+    pub direction: &'static str,
+    pub attempts: u32,
+    pub remaining_min: u32,
+    pub remaining_max: u32,
+}
+
+/// Managed application state: the game lives behind a mutex so every
+/// command sees and mutates the same `GameState` instead of a clone.
+pub struct ManagedGame(pub Arc<Mutex<GameState>>);
+
+impl Default for ManagedGame {
+    fn default() -> Self {
+        ManagedGame(Arc::new(Mutex::new(GameState::new())))
+    }
+}
+
+/// Managed application state for the persistent statistics subsystem. The
+/// data dir is resolved once in `main`'s `setup` closure and kept around so
+/// commands can save back to the same file they were loaded from.
+pub struct ManagedStats {
+    pub stats: Mutex<stats::GameStats>,
+    pub data_dir: PathBuf,
 }
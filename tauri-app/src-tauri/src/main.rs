@@ -1,26 +1,144 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use tauri_app_lib::GameState;
+use std::time::Duration;
+use tauri::Manager;
+use tauri_app_lib::{
+    stats, stats::GameStats, Difficulty, GameError, GameState, GuessFeedback, ManagedGame,
+    ManagedStats,
+};
+
+/// Player is considered stalled, and gets a hint, after this long without a guess.
+const HINT_STALL: Duration = Duration::from_secs(15);
 
 #[tauri::command]
-fn start_game() -> GameState {
-    GameState::new()
+fn start_game(
+    state: tauri::State<'_, ManagedGame>,
+    stats_state: tauri::State<'_, ManagedStats>,
+    difficulty: Difficulty,
+) -> Result<GameState, GameError> {
+    let (min, max) = difficulty.range();
+    let new_game = GameState::with_range(min, max)?;
+    let mut game_state = state.0.lock().unwrap();
+
+    if !game_state.solved() && game_state.attempts() > 0 {
+        let mut stats = stats_state.stats.lock().unwrap();
+        stats.record_game(game_state.attempts(), false);
+        if let Err(err) = stats::save(&stats, &stats_state.data_dir) {
+            eprintln!("failed to save stats: {err}");
+        }
+    }
+
+    *game_state = new_game;
+    Ok(game_state.clone())
+}
+
+#[tauri::command]
+fn make_guess(
+    app_handle: tauri::AppHandle,
+    game: tauri::State<'_, ManagedGame>,
+    stats_state: tauri::State<'_, ManagedStats>,
+    guess: u32,
+) -> Result<(String, u32), GameError> {
+    let mut game_state = game.0.lock().unwrap();
+    let result = game_state.guess(guess)?;
+
+    let direction = if game_state.solved() {
+        "correct"
+    } else if result.0.starts_with("Too low") {
+        "higher"
+    } else {
+        "lower"
+    };
+    let (remaining_min, remaining_max) = game_state.bounds();
+    let _ = app_handle.emit_all(
+        "guess-feedback",
+        GuessFeedback {
+            direction,
+            attempts: result.1,
+            remaining_min,
+            remaining_max,
+        },
+    );
+
+    if game_state.solved() {
+        let mut stats = stats_state.stats.lock().unwrap();
+        stats.record_game(result.1, true);
+        if let Err(err) = stats::save(&stats, &stats_state.data_dir) {
+            eprintln!("failed to save stats: {err}");
+        }
+    }
+
+    Ok(result)
 }
 
 #[tauri::command]
-fn make_guess(state: tauri::State<'_, GameState>, guess: u32) -> (String, u32) {
-    let mut game_state = state.inner().clone();
-    let result = game_state.guess(guess);
-    result
+fn get_stats(stats_state: tauri::State<'_, ManagedStats>) -> GameStats {
+    stats_state.stats.lock().unwrap().clone()
+}
+
+/// Read an arg's value as a string, if the user passed it on the command line.
+fn cli_arg_str(matches: &tauri::api::cli::Matches, name: &str) -> Option<String> {
+    matches
+        .args
+        .get(name)
+        .and_then(|arg| arg.value.as_str())
+        .map(str::to_string)
+}
+
+/// Resolve the `--difficulty`/`--min`/`--max` CLI flags into a `Difficulty`,
+/// preferring an explicit custom range over the named difficulty.
+fn cli_difficulty(matches: &tauri::api::cli::Matches) -> Difficulty {
+    let min = cli_arg_str(matches, "min").and_then(|s| s.parse().ok());
+    let max = cli_arg_str(matches, "max").and_then(|s| s.parse().ok());
+    if let (Some(min), Some(max)) = (min, max) {
+        return Difficulty::Custom { min, max };
+    }
+    match cli_arg_str(matches, "difficulty").as_deref() {
+        Some("easy") => Difficulty::Easy,
+        Some("hard") => Difficulty::Hard,
+        _ => Difficulty::Medium,
+    }
 }
 
 fn main() {
+    let managed_game = ManagedGame::default();
+    let game_handle = managed_game.0.clone();
+
     tauri::Builder::default()
-        .manage(GameState::new())
+        .manage(managed_game)
+        .setup(move |app| {
+            if let Ok(matches) = app.get_cli_matches() {
+                let difficulty = cli_difficulty(&matches);
+                let seed = cli_arg_str(&matches, "seed").and_then(|s| s.parse::<u64>().ok());
+                let (min, max) = difficulty.range();
+                let mut game_state = game_handle.lock().unwrap();
+                *game_state = GameState::with_range_and_seed(min, max, seed)?;
+            }
+
+            let app_data_dir = app.path_resolver().app_data_dir().expect("no app data dir");
+            std::fs::create_dir_all(&app_data_dir)?;
+            let stats = stats::load(&app_data_dir).unwrap_or_default();
+            app.manage(ManagedStats {
+                stats: std::sync::Mutex::new(stats),
+                data_dir: app_data_dir,
+            });
+
+            let app_handle = app.handle();
+            std::thread::spawn(move || loop {
+                std::thread::sleep(Duration::from_secs(1));
+                let mut game_state = game_handle.lock().unwrap();
+                if game_state.should_hint(HINT_STALL) {
+                    let _ = app_handle.emit_all("hint", game_state.hint());
+                }
+            });
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             start_game,
-            make_guess
+            make_guess,
+            get_stats
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
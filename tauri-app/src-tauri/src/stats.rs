@@ -0,0 +1,64 @@
+use crate::GameError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Aggregate statistics for all games played, persisted as JSON under the
+/// app data dir.
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct GameStats {
+    pub games_played: u32,
+    pub games_won: u32,
+    pub total_attempts: u32,
+    pub best_attempts: Option<u32>,
+    /// Average attempts per won game, or `0.0` if nothing has been won yet.
+    /// Kept as a field (not just a method) so `get_stats()` can serialize it
+    /// straight to the frontend. Defaulted on deserialize so stats files
+    /// written before this field existed still load.
+    #[serde(default)]
+    pub average_attempts: f64,
+    pub win_history: Vec<u32>,
+}
+
+impl GameStats {
+    /// Record a finished game, updating totals, the best score and the
+    /// win history. The win history keeps the attempts of every won game;
+    /// it is not capped. `won` distinguishes a solved game from one
+    /// abandoned by starting a new one mid-guess, so `games_played` can
+    /// exceed `games_won`.
+    pub fn record_game(&mut self, attempts: u32, won: bool) {
+        self.games_played += 1;
+        if won {
+            self.games_won += 1;
+            self.total_attempts += attempts;
+            self.best_attempts = Some(match self.best_attempts {
+                Some(best) => best.min(attempts),
+                None => attempts,
+            });
+            self.win_history.push(attempts);
+            self.average_attempts = self.total_attempts as f64 / self.games_won as f64;
+        }
+    }
+}
+
+fn stats_file_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join("stats.json")
+}
+
+/// Load stats from disk, returning `GameStats::default()` if no file exists
+/// yet.
+pub fn load(app_data_dir: &Path) -> Result<GameStats, GameError> {
+    let path = stats_file_path(app_data_dir);
+    if !path.exists() {
+        return Ok(GameStats::default());
+    }
+    let contents = fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Persist stats to disk, creating the app data dir if necessary.
+pub fn save(stats: &GameStats, app_data_dir: &Path) -> Result<(), GameError> {
+    fs::create_dir_all(app_data_dir)?;
+    let contents = serde_json::to_string_pretty(stats)?;
+    fs::write(stats_file_path(app_data_dir), contents)?;
+    Ok(())
+}